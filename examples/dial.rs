@@ -4,20 +4,18 @@ use aesor::*;
 use image::{ImageFormat, Rgba, RgbaImage};
 
 fn main() {
-    let setting = Setting {
-        incident: Vec3::new(0.2, 1., -0.2),
-        ambient_brightness: 0.8,
-        distance: 2000,
-    };
+    let setting = Setting::single_light(2000, Vec3::new(0.2, 1., -0.2), 0.8);
     let white = Material {
         color: Rgba([255, 255, 255, 255]),
         shininess: 7,
         reflection_brightness: 1.,
+        blend_mode: BlendMode::SrcOver,
     };
     let black = Material {
         color: Rgba([0, 0, 0, 255]),
         shininess: 7,
         reflection_brightness: 1.,
+        blend_mode: BlendMode::SrcOver,
     };
 
     let mut img = RgbaImage::new(300, 300);
@@ -4,20 +4,22 @@ use aesor::*;
 use image::{ImageFormat, Rgba, RgbaImage};
 
 fn main() {
-    let setting = Setting {
-        incident: Vec3::new(0.2, 1., -0.2),
-        ambient_brightness: 0.8,
-        distance: 2000,
-    };
+    let setting = Setting::single_light(2000, Vec3::new(0.2, 1., -0.2), 0.8);
     let black = Material {
         color: Rgba([0, 0, 0, 255]),
         shininess: 7,
         reflection_brightness: 1.,
+        blend_mode: BlendMode::SrcOver,
     };
     let blue = Material {
         color: Rgba([179, 220, 214, 255]),
         shininess: 4,
         reflection_brightness: 0.2,
+        blend_mode: BlendMode::SrcOver,
+    };
+    let blue_trace = Material {
+        blend_mode: BlendMode::Multiply,
+        ..blue.clone()
     };
 
     let mut img = RgbaImage::new(300, 200);
@@ -34,7 +36,8 @@ fn main() {
         border_radius: 25.,
         depth: 25.,
     };
-    vec![rim, trace].with(blue).draw(&setting, &mut img);
+    rim.with(blue).draw(&setting, &mut img);
+    trace.with(blue_trace).draw(&setting, &mut img);
 
     let slider_rim = RoundBox {
         top_left: point(50., 100.),
@@ -0,0 +1,109 @@
+use image::{Pixel, Rgba};
+use serde::Deserialize;
+
+/// Porter-Duff / Photoshop-style blend modes. `SrcOver` is the plain alpha
+/// compositing the renderer always used to use; the others combine the
+/// fragment color with the pixel already in the image before compositing.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlendMode {
+    SrcOver,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    Add,
+    Difference,
+}
+
+impl BlendMode {
+    fn mix(&self, src: f64, dst: f64) -> f64 {
+        match self {
+            BlendMode::SrcOver => src,
+            BlendMode::Multiply => src * dst,
+            BlendMode::Screen => src + dst - src * dst,
+            BlendMode::Overlay => {
+                if dst <= 0.5 {
+                    2. * src * dst
+                } else {
+                    1. - 2. * (1. - src) * (1. - dst)
+                }
+            }
+            BlendMode::Darken => src.min(dst),
+            BlendMode::Lighten => src.max(dst),
+            BlendMode::Add => (src + dst).min(1.),
+            BlendMode::Difference => (src - dst).abs(),
+        }
+    }
+
+    /// Composites `src` over `dst`, blending their color channels with
+    /// `mix` and folding alpha the way Porter-Duff `over` does.
+    pub fn composite(&self, src: Rgba<u8>, dst: Rgba<u8>) -> Rgba<u8> {
+        if *self == BlendMode::SrcOver {
+            let mut out = dst;
+            out.blend(&src);
+            return out;
+        }
+
+        let src_a = src[3] as f64 / 255.;
+        let dst_a = dst[3] as f64 / 255.;
+        let out_a = src_a + dst_a * (1. - src_a);
+
+        let mut out = [0u8; 4];
+        for c in 0..3 {
+            let s = src[c] as f64 / 255.;
+            let d = dst[c] as f64 / 255.;
+            let blended = self.mix(s, d);
+            let composed = src_a * (1. - dst_a) * s + src_a * dst_a * blended + (1. - src_a) * dst_a * d;
+            let channel = if out_a > 0. { composed / out_a } else { 0. };
+            out[c] = (channel * 255.).min(255.) as u8;
+        }
+        out[3] = (out_a * 255.).min(255.) as u8;
+        Rgba(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mix_multiply_darkens() {
+        assert_eq!(BlendMode::Multiply.mix(0.5, 0.5), 0.25);
+        assert_eq!(BlendMode::Multiply.mix(1., 0.4), 0.4);
+    }
+
+    #[test]
+    fn mix_screen_lightens() {
+        assert_eq!(BlendMode::Screen.mix(0., 1.), 1.);
+        assert_eq!(BlendMode::Screen.mix(0.5, 0.5), 0.75);
+    }
+
+    #[test]
+    fn mix_darken_and_lighten_pick_extremes() {
+        assert_eq!(BlendMode::Darken.mix(0.2, 0.8), 0.2);
+        assert_eq!(BlendMode::Lighten.mix(0.2, 0.8), 0.8);
+    }
+
+    #[test]
+    fn src_over_with_opaque_dst_keeps_dst_untouched_by_fully_transparent_src() {
+        let dst = Rgba([10, 20, 30, 255]);
+        let src = Rgba([255, 0, 0, 0]);
+        assert_eq!(BlendMode::SrcOver.composite(src, dst), dst);
+    }
+
+    #[test]
+    fn multiply_over_opaque_white_dst_returns_src() {
+        let dst = Rgba([255, 255, 255, 255]);
+        let src = Rgba([10, 20, 30, 255]);
+        assert_eq!(BlendMode::Multiply.composite(src, dst), src);
+    }
+
+    #[test]
+    fn compositing_onto_fully_transparent_dst_yields_src_color() {
+        let dst = Rgba([0, 0, 0, 0]);
+        let src = Rgba([200, 100, 50, 255]);
+        assert_eq!(BlendMode::Screen.composite(src, dst), src);
+    }
+}
@@ -0,0 +1,44 @@
+use image::RgbaImage;
+
+use crate::{Draw, Object, Setting};
+
+/// Owns an `RgbaImage` together with a per-pixel depth buffer, so several
+/// `Object`s can be drawn into one frame with correct occlusion instead of
+/// the caller having to rely on draw order.
+pub struct Canvas {
+    pub(crate) image: RgbaImage,
+    pub(crate) depth: Vec<f64>,
+    pub(crate) painters_order: bool,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32) -> Self {
+        Canvas {
+            image: RgbaImage::new(width, height),
+            depth: vec![f64::NEG_INFINITY; (width * height) as usize],
+            painters_order: false,
+        }
+    }
+
+    /// Same as `new`, but disables the depth test so objects are composited
+    /// in call order, matching the renderer's behavior before the depth
+    /// buffer was introduced.
+    pub fn painters_order(width: u32, height: u32) -> Self {
+        Canvas {
+            painters_order: true,
+            ..Self::new(width, height)
+        }
+    }
+
+    pub fn draw<T: Draw>(&mut self, object: &Object<T>, setting: &Setting) {
+        object.shape.draw_depth(setting, &object.material, self);
+    }
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    pub fn into_image(self) -> RgbaImage {
+        self.image
+    }
+}
@@ -0,0 +1,13 @@
+use image::Rgba;
+use serde::{Deserialize, Deserializer};
+
+/// `image::Rgba<u8>` has no serde support of its own, so fields typed as it
+/// need `#[serde(with = "crate::color")]` to deserialize from a plain
+/// `[r, g, b, a]` array instead.
+pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Rgba<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let channels = <[u8; 4]>::deserialize(deserializer)?;
+    Ok(Rgba(channels))
+}
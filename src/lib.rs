@@ -0,0 +1,18 @@
+mod blend;
+mod canvas;
+mod color;
+mod core;
+mod light;
+mod scene;
+mod transform;
+
+pub mod march;
+pub mod shape;
+pub mod util;
+
+pub use blend::BlendMode;
+pub use canvas::Canvas;
+pub use core::*;
+pub use light::Light;
+pub use scene::{Scene, SceneError, SceneFile, SceneFormat, ShapeSpec};
+pub use transform::{Axis, Mat3, Transform, Transformed};
@@ -0,0 +1,46 @@
+use image::Rgba;
+use serde::Deserialize;
+
+use crate::util::Vec3;
+
+/// A light contributing to the scene. `Directional` mirrors the sun-like
+/// single light the renderer used to hard-code; `Point` computes a
+/// per-sample direction and falls off with inverse-square distance.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Light {
+    Directional {
+        dir: Vec3,
+        #[serde(with = "crate::color")]
+        color: Rgba<u8>,
+        intensity: f64,
+    },
+    Point {
+        pos: Vec3,
+        #[serde(with = "crate::color")]
+        color: Rgba<u8>,
+        intensity: f64,
+    },
+}
+
+impl Light {
+    /// Direction of incidence, color and attenuated intensity at `p`.
+    pub fn sample(&self, p: &Vec3) -> (Vec3, Rgba<u8>, f64) {
+        match self {
+            Light::Directional {
+                dir,
+                color,
+                intensity,
+            } => (dir.clone(), *color, *intensity),
+            Light::Point {
+                pos,
+                color,
+                intensity,
+            } => {
+                let to_p = p - pos;
+                let dist = to_p.norm();
+                (to_p.normal(), *color, intensity / dist.powi(2).max(1.))
+            }
+        }
+    }
+}
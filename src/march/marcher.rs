@@ -0,0 +1,106 @@
+use image::{Rgba, RgbaImage};
+
+use crate::core::{shade_normal, sight};
+use crate::util::Vec3;
+use crate::{Material, Setting};
+
+use super::sdf::Sdf;
+
+/// Renders an `Sdf` by sphere-tracing rather than reconstructing a normal
+/// from an analytic heightmap formula, so it supports true occlusion,
+/// self-shadowing and arbitrary solids the `Reflect`-based shapes can't.
+pub struct RayMarcher<S: Sdf> {
+    pub sdf: S,
+    pub material: Material,
+    pub max_steps: u32,
+    pub max_distance: f64,
+    pub epsilon: f64,
+}
+
+impl<S: Sdf> RayMarcher<S> {
+    pub fn new(sdf: S, material: Material) -> Self {
+        RayMarcher {
+            sdf,
+            material,
+            max_steps: 128,
+            max_distance: 1000.,
+            epsilon: 1e-3,
+        }
+    }
+
+    fn march(&self, origin: &Vec3, dir: &Vec3, max_distance: f64) -> Option<(Vec3, f64)> {
+        let mut t = 0.;
+        for _ in 0..self.max_steps {
+            let p = origin + &(t * dir);
+            let d = self.sdf.dist(&p);
+            if d < self.epsilon {
+                return Some((p, t));
+            }
+            t += d;
+            if t > max_distance {
+                break;
+            }
+        }
+        None
+    }
+
+    /// Estimates the surface normal at `p` from central differences of
+    /// `dist` along each axis.
+    fn normal(&self, p: &Vec3) -> Vec3 {
+        let e = 1e-4;
+        let axis = |offset: Vec3| self.sdf.dist(&(p + &offset));
+        Vec3::new(
+            axis(Vec3::new(e, 0., 0.)) - axis(Vec3::new(-e, 0., 0.)),
+            axis(Vec3::new(0., e, 0.)) - axis(Vec3::new(0., -e, 0.)),
+            axis(Vec3::new(0., 0., e)) - axis(Vec3::new(0., 0., -e)),
+        )
+        .normal()
+    }
+
+    /// Marches from `p` toward a light along `to_light`, tracking the
+    /// minimum `k * dist / t` ratio seen along the way so near-misses
+    /// darken the penumbra instead of producing a hard shadow edge.
+    fn soft_shadow(&self, p: &Vec3, to_light: &Vec3, k: f64, max_distance: f64) -> f64 {
+        let mut t = self.epsilon * 4.;
+        let mut shadow = 1.0f64;
+        for _ in 0..self.max_steps {
+            let sample = p + &(t * to_light);
+            let d = self.sdf.dist(&sample);
+            if d < self.epsilon {
+                return 0.;
+            }
+            shadow = shadow.min(k * d / t);
+            t += d;
+            if t > max_distance {
+                break;
+            }
+        }
+        shadow.clamp(0., 1.)
+    }
+
+    pub fn render(&self, setting: &Setting, width: u32, height: u32) -> RgbaImage {
+        let mut img = RgbaImage::new(width, height);
+        let origin = Vec3::new(width as f64 / 2., height as f64 / 2., setting.distance as f64);
+        // The camera sits `setting.distance` away from the geometry near
+        // z = 0, so the march needs at least that much travel budget; the
+        // `max_distance` field alone (sized for a typical scene's own
+        // geometry, not the camera offset) would otherwise leave every ray
+        // stopping short and `render` returning a blank image.
+        let max_distance = self.max_distance.max(setting.distance as f64 * 2.);
+        for (x, y, px) in img.enumerate_pixels_mut() {
+            let dir = sight(x as f64, y as f64, width, height, setting.distance);
+            let Some((p, _t)) = self.march(&origin, &dir, max_distance) else {
+                continue;
+            };
+            let n = self.normal(&p);
+            let bias = &p + &((2. * self.epsilon) * &n);
+            let [r, g, b] = shade_normal(&p, &n, &dir, setting, &self.material, |light_dir| {
+                let to_light = -light_dir;
+                self.soft_shadow(&bias, &to_light, 16., max_distance)
+            });
+            let channel = |v: f64| v.min(255.) as u8;
+            *px = Rgba([channel(r), channel(g), channel(b), self.material.color[3]]);
+        }
+        img
+    }
+}
@@ -0,0 +1,5 @@
+mod marcher;
+mod sdf;
+
+pub use marcher::RayMarcher;
+pub use sdf::{Cuboid, Sdf, SmoothUnion, Sphere, Torus, Union};
@@ -0,0 +1,155 @@
+use crate::util::Vec3;
+
+/// A signed distance field: `dist` returns the distance from `p` to the
+/// nearest surface, negative when `p` is inside. The `RayMarcher` sphere-
+/// traces by repeatedly stepping by this distance.
+pub trait Sdf {
+    fn dist(&self, p: &Vec3) -> f64;
+}
+
+impl<T: Sdf + ?Sized> Sdf for Box<T> {
+    fn dist(&self, p: &Vec3) -> f64 {
+        self.as_ref().dist(p)
+    }
+}
+
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f64,
+}
+
+impl Sdf for Sphere {
+    fn dist(&self, p: &Vec3) -> f64 {
+        (p - &self.center).norm() - self.radius
+    }
+}
+
+pub struct Torus {
+    pub center: Vec3,
+    pub major: f64,
+    pub minor: f64,
+}
+
+impl Sdf for Torus {
+    fn dist(&self, p: &Vec3) -> f64 {
+        let p = p - &self.center;
+        let qx = (p.x.powi(2) + p.z.powi(2)).sqrt() - self.major;
+        let qy = p.y;
+        (qx.powi(2) + qy.powi(2)).sqrt() - self.minor
+    }
+}
+
+pub struct Cuboid {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+}
+
+impl Sdf for Cuboid {
+    fn dist(&self, p: &Vec3) -> f64 {
+        let p = p - &self.center;
+        let qx = p.x.abs() - self.half_extents.x;
+        let qy = p.y.abs() - self.half_extents.y;
+        let qz = p.z.abs() - self.half_extents.z;
+        let outside = (qx.max(0.).powi(2) + qy.max(0.).powi(2) + qz.max(0.).powi(2)).sqrt();
+        let inside = qx.max(qy).max(qz).min(0.);
+        outside + inside
+    }
+}
+
+/// The hard minimum of two SDFs, i.e. the shape that's solid wherever
+/// either `a` or `b` is.
+pub struct Union<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A: Sdf, B: Sdf> Sdf for Union<A, B> {
+    fn dist(&self, p: &Vec3) -> f64 {
+        self.a.dist(p).min(self.b.dist(p))
+    }
+}
+
+/// Like `Union`, but blends the seam over a radius `k` (Quilez's polynomial
+/// smooth minimum) instead of leaving a hard crease.
+pub struct SmoothUnion<A, B> {
+    pub a: A,
+    pub b: B,
+    pub k: f64,
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothUnion<A, B> {
+    fn dist(&self, p: &Vec3) -> f64 {
+        let d1 = self.a.dist(p);
+        let d2 = self.b.dist(p);
+        let h = (0.5 + 0.5 * (d2 - d1) / self.k).clamp(0., 1.);
+        d2 * (1. - h) + d1 * h - self.k * h * (1. - h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sphere_dist_is_signed() {
+        let sphere = Sphere {
+            center: Vec3::new(0., 0., 0.),
+            radius: 1.,
+        };
+        assert_eq!(sphere.dist(&Vec3::new(2., 0., 0.)), 1.);
+        assert_eq!(sphere.dist(&Vec3::new(0., 0., 0.)), -1.);
+        assert_eq!(sphere.dist(&Vec3::new(1., 0., 0.)), 0.);
+    }
+
+    #[test]
+    fn torus_dist_at_ring_center_is_negative_minor_radius() {
+        let torus = Torus {
+            center: Vec3::new(0., 0., 0.),
+            major: 2.,
+            minor: 0.5,
+        };
+        assert!((torus.dist(&Vec3::new(2., 0., 0.)) - -0.5).abs() < 1e-9);
+        assert!((torus.dist(&Vec3::new(2.5, 0., 0.)) - 0.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cuboid_dist_at_face_and_corner() {
+        let cuboid = Cuboid {
+            center: Vec3::new(0., 0., 0.),
+            half_extents: Vec3::new(1., 2., 3.),
+        };
+        assert!((cuboid.dist(&Vec3::new(1., 0., 0.)) - 0.).abs() < 1e-9);
+        assert!(cuboid.dist(&Vec3::new(0., 0., 0.)) < 0.);
+        assert!((cuboid.dist(&Vec3::new(2., 3., 4.)) - 3f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn union_picks_nearer_surface() {
+        let union = Union {
+            a: Sphere {
+                center: Vec3::new(-5., 0., 0.),
+                radius: 1.,
+            },
+            b: Sphere {
+                center: Vec3::new(5., 0., 0.),
+                radius: 1.,
+            },
+        };
+        assert_eq!(union.dist(&Vec3::new(3., 0., 0.)), union.b.dist(&Vec3::new(3., 0., 0.)));
+    }
+
+    #[test]
+    fn smooth_union_matches_hard_union_far_from_the_seam() {
+        let a = Sphere {
+            center: Vec3::new(-5., 0., 0.),
+            radius: 1.,
+        };
+        let b = Sphere {
+            center: Vec3::new(5., 0., 0.),
+            radius: 1.,
+        };
+        let smooth = SmoothUnion { a, b, k: 0.1 };
+        let p = Vec3::new(-5., 0., 0.);
+        assert!((smooth.dist(&p) - -1.).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use image::RgbaImage;
+use serde::Deserialize;
+
+use crate::canvas::Canvas;
+use crate::core::{Draw, Material, Object, Setting};
+use crate::shape::{Concave, Corn, RoundBox};
+use crate::util::Vec3;
+
+/// One entry of `SceneFile::objects`, tagged by `"type"` so scene authors
+/// can list `Corn`/`Concave`/`RoundBox` shapes by name and reference a
+/// material from `SceneFile::materials` instead of constructing it inline.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ShapeSpec {
+    Corn {
+        center: Vec3,
+        radius: f64,
+        height: f64,
+        material: String,
+    },
+    Concave {
+        center: Vec3,
+        radius: f64,
+        depth: f64,
+        material: String,
+    },
+    RoundBox {
+        top_left: Vec3,
+        bottom_right: Vec3,
+        border_radius: f64,
+        depth: f64,
+        material: String,
+    },
+}
+
+impl ShapeSpec {
+    fn material_name(&self) -> &str {
+        match self {
+            ShapeSpec::Corn { material, .. }
+            | ShapeSpec::Concave { material, .. }
+            | ShapeSpec::RoundBox { material, .. } => material,
+        }
+    }
+
+    fn into_object(self, material: Material) -> Object<Box<dyn Draw>> {
+        let shape: Box<dyn Draw> = match self {
+            ShapeSpec::Corn {
+                center,
+                radius,
+                height,
+                ..
+            } => Box::new(Corn {
+                center,
+                radius,
+                height,
+            }),
+            ShapeSpec::Concave {
+                center,
+                radius,
+                depth,
+                ..
+            } => Box::new(Concave {
+                center,
+                radius,
+                depth,
+            }),
+            ShapeSpec::RoundBox {
+                top_left,
+                bottom_right,
+                border_radius,
+                depth,
+                ..
+            } => Box::new(RoundBox {
+                top_left,
+                bottom_right,
+                border_radius,
+                depth,
+            }),
+        };
+        Object { shape, material }
+    }
+}
+
+/// The deserialized shape of a scene file: a `Setting`, a table of named
+/// materials, and the objects that reference them.
+#[derive(Deserialize)]
+pub struct SceneFile {
+    pub width: u32,
+    pub height: u32,
+    pub setting: Setting,
+    pub materials: HashMap<String, Material>,
+    pub objects: Vec<ShapeSpec>,
+}
+
+/// The format a scene file is written in. `Scene::from_path` picks this
+/// from the file extension; `Scene::from_reader` takes it explicitly since
+/// a reader has no extension to inspect.
+#[derive(Clone, Copy)]
+pub enum SceneFormat {
+    Json,
+    Toml,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
+    UnknownMaterial(String),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "failed to read scene file: {e}"),
+            SceneError::Json(e) => write!(f, "failed to parse scene as JSON: {e}"),
+            SceneError::Toml(e) => write!(f, "failed to parse scene as TOML: {e}"),
+            SceneError::UnknownMaterial(name) => {
+                write!(f, "object references unknown material `{name}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<std::io::Error> for SceneError {
+    fn from(e: std::io::Error) -> Self {
+        SceneError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SceneError {
+    fn from(e: serde_json::Error) -> Self {
+        SceneError::Json(e)
+    }
+}
+
+impl From<toml::de::Error> for SceneError {
+    fn from(e: toml::de::Error) -> Self {
+        SceneError::Toml(e)
+    }
+}
+
+/// A scene resolved from a `SceneFile`: materials have been looked up by
+/// name and every `ShapeSpec` turned into a boxed `Object` ready to draw.
+pub struct Scene {
+    width: u32,
+    height: u32,
+    setting: Setting,
+    objects: Vec<Object<Box<dyn Draw>>>,
+}
+
+impl Scene {
+    pub fn from_reader<R: Read>(mut reader: R, format: SceneFormat) -> Result<Self, SceneError> {
+        let mut contents = String::new();
+        reader.read_to_string(&mut contents)?;
+        let file: SceneFile = match format {
+            SceneFormat::Json => serde_json::from_str(&contents)?,
+            SceneFormat::Toml => toml::from_str(&contents)?,
+        };
+        Self::from_file(file)
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        let format = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => SceneFormat::Toml,
+            _ => SceneFormat::Json,
+        };
+        Self::from_reader(File::open(path)?, format)
+    }
+
+    fn from_file(file: SceneFile) -> Result<Self, SceneError> {
+        let mut objects = Vec::with_capacity(file.objects.len());
+        for spec in file.objects {
+            let material = file
+                .materials
+                .get(spec.material_name())
+                .cloned()
+                .ok_or_else(|| SceneError::UnknownMaterial(spec.material_name().to_string()))?;
+            objects.push(spec.into_object(material));
+        }
+        Ok(Scene {
+            width: file.width,
+            height: file.height,
+            setting: file.setting,
+            objects,
+        })
+    }
+
+    pub fn render(&self) -> RgbaImage {
+        let mut canvas = Canvas::new(self.width, self.height);
+        for object in &self.objects {
+            canvas.draw(object, &self.setting);
+        }
+        canvas.into_image()
+    }
+}
@@ -8,7 +8,7 @@ pub struct Concave {
 }
 
 impl Reflect for Concave {
-    fn normal_vec(&self, p: &Vec3) -> Option<(Vec3, f64)> {
+    fn normal_vec(&self, p: &Vec3) -> Option<(Vec3, f64, f64)> {
         let p = p - &self.center;
         if p.norm() <= self.radius {
             let y0 = (self.radius.powi(2) - self.depth.powi(2)) / (2. * self.depth.abs());
@@ -16,7 +16,13 @@ impl Reflect for Concave {
             let theta = (p.norm() / r).asin();
             let p_sign = -1. * self.depth / self.depth.abs();
             let n = Vec3::new(p_sign * p.x, p_sign * p.y, r * theta.cos()).normal();
-            Some((n, (self.radius - p.norm()).min(1.)))
+            // Carry depth's sign into the elevation too, not just the
+            // normal: a dish's surface sits below the rim (z < 0) and a
+            // bump's sits above it (z > 0), so depth buffers compare
+            // correctly against other shapes instead of both reading as
+            // `|depth|` at the center.
+            let z = p_sign * (r * theta.cos() - y0);
+            Some((n, (self.radius - p.norm()).min(1.), z))
         } else {
             None
         }
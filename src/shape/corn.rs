@@ -8,11 +8,12 @@ pub struct Corn {
 }
 
 impl Reflect for Corn {
-    fn normal_vec(&self, p: &Vec3) -> Option<(Vec3, f64)> {
+    fn normal_vec(&self, p: &Vec3) -> Option<(Vec3, f64, f64)> {
         let p = p - &self.center;
         if p.norm() <= self.radius {
             let n = Vec3::new(self.height, 0., self.radius).rot_xy(&p).normal();
-            Some((n, (self.radius - p.norm()).min(1.)))
+            let z = self.height * (1. - p.norm() / self.radius);
+            Some((n, (self.radius - p.norm()).min(1.), z))
         } else {
             None
         }
@@ -0,0 +1,7 @@
+mod concave;
+mod corn;
+mod round_box;
+
+pub use concave::Concave;
+pub use corn::Corn;
+pub use round_box::RoundBox;
@@ -10,7 +10,7 @@ pub struct RoundBox {
 }
 
 impl Reflect for RoundBox {
-    fn normal_vec(&self, p: &Vec3) -> Option<(Vec3, f64)> {
+    fn normal_vec(&self, p: &Vec3) -> Option<(Vec3, f64, f64)> {
         let (rim_x, rim_y) = if p.x <= self.top_left.x {
             if p.y <= self.top_left.y {
                 (self.top_left.x, self.top_left.y)
@@ -0,0 +1,168 @@
+use std::ops::Mul;
+
+use crate::util::Vec3;
+use crate::Reflect;
+
+/// A row-major 3x3 matrix, used to rotate and scale shapes in `Transformed`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat3 {
+    pub rows: [[f64; 3]; 3],
+}
+
+impl Mat3 {
+    pub fn identity() -> Self {
+        Mat3 {
+            rows: [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+        }
+    }
+
+    pub fn scale(x: f64, y: f64, z: f64) -> Self {
+        Mat3 {
+            rows: [[x, 0., 0.], [0., y, 0.], [0., 0., z]],
+        }
+    }
+
+    pub fn rotation_x(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Mat3 {
+            rows: [[1., 0., 0.], [0., c, -s], [0., s, c]],
+        }
+    }
+
+    pub fn rotation_y(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Mat3 {
+            rows: [[c, 0., s], [0., 1., 0.], [-s, 0., c]],
+        }
+    }
+
+    pub fn rotation_z(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Mat3 {
+            rows: [[c, -s, 0.], [s, c, 0.], [0., 0., 1.]],
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut rows = [[0.; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = self.rows[j][i];
+            }
+        }
+        Mat3 { rows }
+    }
+}
+
+impl Mul<&Vec3> for &Mat3 {
+    type Output = Vec3;
+    fn mul(self, v: &Vec3) -> Vec3 {
+        let r = &self.rows;
+        Vec3::new(
+            r[0][0] * v.x + r[0][1] * v.y + r[0][2] * v.z,
+            r[1][0] * v.x + r[1][1] * v.y + r[1][2] * v.z,
+            r[2][0] * v.x + r[2][1] * v.y + r[2][2] * v.z,
+        )
+    }
+}
+
+impl Mul<&Mat3> for &Mat3 {
+    type Output = Mat3;
+    fn mul(self, rhs: &Mat3) -> Mat3 {
+        let mut rows = [[0.; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| self.rows[i][k] * rhs.rows[k][j]).sum();
+            }
+        }
+        Mat3 { rows }
+    }
+}
+
+/// The axis a `.rotated(axis, angle)` call rotates around.
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// Wraps a shape with a rotation/translation, sampling it by mapping the
+/// world-space point into the shape's local space before delegating.
+pub struct Transformed<T> {
+    pub inner: T,
+    pub inverse: Mat3,
+    pub translation: Vec3,
+}
+
+impl<T: Reflect> Reflect for Transformed<T> {
+    fn normal_vec(&self, p: &Vec3) -> Option<(Vec3, f64, f64)> {
+        let local = &self.inverse * &(p - &self.translation);
+        let (n, alpha, z) = self.inner.normal_vec(&local)?;
+        let world_n = (&self.inverse.transpose() * &n).normal();
+        Some((world_n, alpha, z))
+    }
+}
+
+/// Lets any `Reflect` shape be tilted and repositioned without a per-shape
+/// `center`/rotation field, by wrapping it in `Transformed`.
+pub trait Transform: Reflect + Sized {
+    fn rotated(self, axis: Axis, angle: f64) -> Transformed<Self> {
+        let rotation = match axis {
+            Axis::X => Mat3::rotation_x(angle),
+            Axis::Y => Mat3::rotation_y(angle),
+            Axis::Z => Mat3::rotation_z(angle),
+        };
+        Transformed {
+            inner: self,
+            inverse: rotation.transpose(),
+            translation: Vec3::new(0., 0., 0.),
+        }
+    }
+
+    fn translated(self, v: Vec3) -> Transformed<Self> {
+        Transformed {
+            inner: self,
+            inverse: Mat3::identity(),
+            translation: v,
+        }
+    }
+}
+
+impl<T: Reflect> Transform for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx_eq(a: &Vec3, b: &Vec3) {
+        assert!((a.x - b.x).abs() < 1e-9, "{} != {}", a.x, b.x);
+        assert!((a.y - b.y).abs() < 1e-9, "{} != {}", a.y, b.y);
+        assert!((a.z - b.z).abs() < 1e-9, "{} != {}", a.z, b.z);
+    }
+
+    #[test]
+    fn identity_leaves_vectors_unchanged() {
+        let v = Vec3::new(1., 2., 3.);
+        approx_eq(&(&Mat3::identity() * &v), &v);
+    }
+
+    #[test]
+    fn rotation_z_quarter_turn_maps_x_to_y() {
+        let r = Mat3::rotation_z(std::f64::consts::FRAC_PI_2);
+        approx_eq(&(&r * &Vec3::new(1., 0., 0.)), &Vec3::new(0., 1., 0.));
+    }
+
+    #[test]
+    fn rotation_is_orthogonal_so_transpose_is_its_inverse() {
+        let r = Mat3::rotation_x(0.7);
+        let product = &r * &r.transpose();
+        approx_eq(&(&product * &Vec3::new(1., 0., 0.)), &Vec3::new(1., 0., 0.));
+        approx_eq(&(&product * &Vec3::new(0., 1., 0.)), &Vec3::new(0., 1., 0.));
+    }
+
+    #[test]
+    fn scale_multiplies_each_axis() {
+        let s = Mat3::scale(2., 3., 4.);
+        approx_eq(&(&s * &Vec3::new(1., 1., 1.)), &Vec3::new(2., 3., 4.));
+    }
+}
@@ -1,6 +1,8 @@
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
-#[derive(Clone)]
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize)]
 pub struct Vec3 {
     pub x: f64,
     pub y: f64,